@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::{oneshot, Mutex};
+
+use crate::config::{self, atomic_write, config_dir, normalize_base_url, ConfigError};
+use crate::tls::{build_http_client, TlsError};
+
+const CODE_VERIFIER_LEN: usize = 64;
+const UNRESERVED_CHARS: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+const REDIRECT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("http error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("tls setup failed: {0}")]
+    Tls(#[from] TlsError),
+
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("no login in progress, call begin_login first")]
+    NotStarted,
+
+    #[error("redirect state did not match the request that started this login")]
+    StateMismatch,
+
+    #[error("timed out waiting for the browser to redirect back")]
+    Timeout,
+
+    #[error("authorization server rejected the login: {0}")]
+    ServerError(String),
+
+    #[error("failed to open the system browser: {0}")]
+    Browser(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenSet {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+struct CallbackParams {
+    code: String,
+    state: String,
+}
+
+struct PendingLogin {
+    code_verifier: String,
+    csrf_state: String,
+    redirect_uri: String,
+    server_url: String,
+    callback_rx: oneshot::Receiver<Result<CallbackParams, String>>,
+}
+
+/// Tracks the single in-flight PKCE login, if any, between `begin_login` and `complete_login`.
+#[derive(Default)]
+pub struct AuthManager {
+    pending: Mutex<Option<PendingLogin>>,
+}
+
+fn generate_token(len: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..len)
+        .map(|_| UNRESERVED_CHARS[rng.gen_range(0..UNRESERVED_CHARS.len())] as char)
+        .collect()
+}
+
+fn code_challenge_for(verifier: &str) -> String {
+    let digest = Sha256::digest(verifier.as_bytes());
+    URL_SAFE_NO_PAD.encode(digest)
+}
+
+fn tokens_file_path() -> Result<std::path::PathBuf, AuthError> {
+    Ok(config_dir()?.join("tokens.json"))
+}
+
+/// Opens `{server_url}/authorize` in the system browser to kick off a PKCE login and starts a
+/// one-shot loopback listener to catch the redirect. Call `complete_login` afterwards to finish
+/// the exchange once the user has authorized in the browser.
+#[tauri::command]
+pub async fn begin_login(
+    server_url: String,
+    auth: tauri::State<'_, AuthManager>,
+) -> Result<String, String> {
+    begin_login_inner(server_url, auth.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn begin_login_inner(server_url: String, auth: &AuthManager) -> Result<String, AuthError> {
+    let base = normalize_base_url(&server_url)?;
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+    let port = listener.local_addr()?.port();
+    let redirect_uri = format!("http://127.0.0.1:{port}/callback");
+
+    let code_verifier = generate_token(CODE_VERIFIER_LEN);
+    let code_challenge = code_challenge_for(&code_verifier);
+    let csrf_state = generate_token(32);
+
+    let mut authorize_url = url::Url::parse(&format!("{base}/authorize"))
+        .map_err(|e| ConfigError::InvalidUrl(format!("{e}")))?;
+    authorize_url
+        .query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("code_challenge", &code_challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("state", &csrf_state)
+        .append_pair("redirect_uri", &redirect_uri);
+
+    let (tx, rx) = oneshot::channel();
+    tokio::spawn(accept_redirect(listener, tx));
+
+    *auth.pending.lock().await = Some(PendingLogin {
+        code_verifier,
+        csrf_state,
+        redirect_uri,
+        server_url: base,
+        callback_rx: rx,
+    });
+
+    let authorize_url = authorize_url.to_string();
+    open::that(&authorize_url).map_err(|e| AuthError::Browser(e.to_string()))?;
+
+    Ok(authorize_url)
+}
+
+async fn accept_redirect(
+    listener: TcpListener,
+    tx: oneshot::Sender<Result<CallbackParams, String>>,
+) {
+    let result = accept_redirect_inner(listener).await;
+    let _ = tx.send(result);
+}
+
+async fn accept_redirect_inner(listener: TcpListener) -> Result<CallbackParams, String> {
+    let (mut stream, _) = listener
+        .accept()
+        .await
+        .map_err(|e| format!("loopback accept failed: {e}"))?;
+
+    let mut buf = [0u8; 4096];
+    let n = stream
+        .read(&mut buf)
+        .await
+        .map_err(|e| format!("loopback read failed: {e}"))?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let request_line = request.lines().next().unwrap_or_default();
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| "malformed redirect request".to_string())?;
+
+    let redirect_url = url::Url::parse(&format!("http://127.0.0.1{path}"))
+        .map_err(|e| format!("malformed redirect url: {e}"))?;
+    let params: HashMap<String, String> = redirect_url.query_pairs().into_owned().collect();
+
+    let body = if params.contains_key("error") {
+        "<html><body>Login failed, you can close this tab.</body></html>"
+    } else {
+        "<html><body>Login complete, you can close this tab.</body></html>"
+    };
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+
+    if let Some(err) = params.get("error") {
+        return Err(err.clone());
+    }
+
+    let code = params
+        .get("code")
+        .cloned()
+        .ok_or_else(|| "redirect is missing the authorization code".to_string())?;
+    let state = params.get("state").cloned().unwrap_or_default();
+    Ok(CallbackParams { code, state })
+}
+
+/// Waits for the browser redirect started by `begin_login`, exchanges the authorization code for
+/// tokens, persists them next to `config.json`, and returns the resulting `TokenSet`.
+#[tauri::command]
+pub async fn complete_login(auth: tauri::State<'_, AuthManager>) -> Result<TokenSet, String> {
+    complete_login_inner(auth.inner())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn complete_login_inner(auth: &AuthManager) -> Result<TokenSet, AuthError> {
+    let pending = auth
+        .pending
+        .lock()
+        .await
+        .take()
+        .ok_or(AuthError::NotStarted)?;
+
+    let callback = tokio::time::timeout(REDIRECT_TIMEOUT, pending.callback_rx)
+        .await
+        .map_err(|_| AuthError::Timeout)?
+        .map_err(|_| AuthError::Timeout)?
+        .map_err(AuthError::ServerError)?;
+
+    if callback.state != pending.csrf_state {
+        return Err(AuthError::StateMismatch);
+    }
+
+    let tls_cfg = config::load()?.and_then(|c| c.tls);
+    let client = build_http_client(tls_cfg.as_ref(), Duration::from_secs(15))?;
+
+    let token_url = format!("{}/token", pending.server_url);
+    let response = client
+        .post(&token_url)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", &callback.code),
+            ("redirect_uri", &pending.redirect_uri),
+            ("code_verifier", &pending.code_verifier),
+        ])
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(AuthError::ServerError(format!("{status}: {body}")));
+    }
+
+    let token: TokenResponse = response.json().await?;
+    let expires_at = token.expires_in.map(|secs| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now + secs
+    });
+
+    let tokens = TokenSet {
+        access_token: token.access_token,
+        refresh_token: token.refresh_token,
+        expires_at,
+    };
+
+    let json = serde_json::to_string_pretty(&tokens).map(|s| format!("{s}\n"))?;
+    atomic_write(&tokens_file_path()?, json.as_bytes())?;
+    config::store_profile_token(&pending.server_url, &tokens.access_token)?;
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_challenge_matches_rfc7636_test_vector() {
+        // https://datatracker.ietf.org/doc/html/rfc7636#appendix-B
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        let challenge = code_challenge_for(verifier);
+        assert_eq!(challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+    }
+
+    #[test]
+    fn generated_verifier_has_requested_length_and_unreserved_charset() {
+        let verifier = generate_token(CODE_VERIFIER_LEN);
+        assert_eq!(verifier.len(), CODE_VERIFIER_LEN);
+        assert!(verifier.bytes().all(|b| UNRESERVED_CHARS.contains(&b)));
+    }
+}
@@ -1,10 +1,82 @@
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+use crate::tls::TlsConfig;
+
+const LOCK_FILE_NAME: &str = ".lock";
+
+const DEFAULT_PROFILE_NAME: &str = "default";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServerProfile {
+    pub name: String,
+    pub base_url: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub headers: Option<HashMap<String, String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(from = "RawAppConfig")]
 pub struct AppConfig {
-    pub kiro_server_url: String,
+    pub profiles: Vec<ServerProfile>,
+    pub active_profile: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub tls: Option<TlsConfig>,
+}
+
+/// Accepts either the current multi-profile shape or the old single-`kiro_server_url` shape, so
+/// a `config.json` written by an older build still loads cleanly.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawAppConfig {
+    Current {
+        profiles: Vec<ServerProfile>,
+        active_profile: String,
+        #[serde(default)]
+        tls: Option<TlsConfig>,
+    },
+    Legacy {
+        kiro_server_url: String,
+    },
+}
+
+impl From<RawAppConfig> for AppConfig {
+    fn from(raw: RawAppConfig) -> Self {
+        match raw {
+            RawAppConfig::Current {
+                profiles,
+                active_profile,
+                tls,
+            } => AppConfig {
+                profiles,
+                active_profile,
+                tls,
+            },
+            RawAppConfig::Legacy { kiro_server_url } => AppConfig {
+                profiles: vec![ServerProfile {
+                    name: DEFAULT_PROFILE_NAME.to_string(),
+                    base_url: kiro_server_url,
+                    token: None,
+                    headers: None,
+                }],
+                active_profile: DEFAULT_PROFILE_NAME.to_string(),
+                tls: None,
+            },
+        }
+    }
+}
+
+impl AppConfig {
+    fn active_profile_mut(&mut self) -> Option<&mut ServerProfile> {
+        let active = self.active_profile.clone();
+        self.profiles.iter_mut().find(|p| p.name == active)
+    }
 }
 
 #[derive(Debug, Error)]
@@ -20,9 +92,15 @@ pub enum ConfigError {
 
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
+
+    #[error("a profile named '{0}' already exists")]
+    DuplicateProfile(String),
+
+    #[error("no profile named '{0}'")]
+    UnknownProfile(String),
 }
 
-fn config_dir() -> Result<PathBuf, ConfigError> {
+pub(crate) fn config_dir() -> Result<PathBuf, ConfigError> {
     let home_dir = dirs::home_dir().ok_or(ConfigError::MissingHomeDir)?;
     Ok(home_dir.join(".config").join("antihook"))
 }
@@ -56,23 +134,104 @@ pub fn normalize_base_url(raw: &str) -> Result<String, ConfigError> {
     Ok(trimmed.to_string())
 }
 
-fn atomic_write(path: &Path, data: &[u8]) -> Result<(), std::io::Error> {
+/// Takes an exclusive advisory lock on `dir` so concurrent writers (e.g. `save_config` racing a
+/// background `add_profile`) serialize instead of clobbering each other. The lock is released
+/// when the returned file is dropped.
+fn lock_dir(dir: &Path) -> Result<std::fs::File, std::io::Error> {
+    let lock_file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(dir.join(LOCK_FILE_NAME))?;
+    lock_file.lock_exclusive()?;
+    Ok(lock_file)
+}
+
+/// Writes `data` to `path` crash-safely: write the temp file, `fsync` it, `rename` it directly
+/// over the target (atomic on POSIX), then `fsync` the parent directory so the rename itself
+/// survives a crash. Assumes `path`'s parent directory already exists and that, if this write
+/// needs to be serialized against a whole read-modify-write cycle rather than just other raw
+/// writes, the caller already holds the parent directory's lock (see `with_locked_config`).
+fn atomic_write_unlocked(path: &Path, data: &[u8]) -> Result<(), std::io::Error> {
     let tmp_path = path.with_extension("json.tmp");
+    {
+        let mut tmp_file = std::fs::File::create(&tmp_path)?;
+        tmp_file.write_all(data)?;
+        tmp_file.sync_all()?;
+    }
+
+    std::fs::rename(&tmp_path, path)?;
 
+    // Opening a directory and flushing its handle is a POSIX-only way to fsync the rename;
+    // Windows has no equivalent, so `fsync_dir` is a no-op there; on POSIX a failure is ignored.
     if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+        fsync_dir(parent);
     }
 
-    std::fs::write(&tmp_path, data)?;
+    Ok(())
+}
+
+/// Writes `data` to `path` crash-safely, taking the parent directory's lock for the duration of the write (see
+/// `atomic_write_unlocked`). Use this for one-off writes outside `AppConfig`'s read-modify-write
+/// cycle, e.g. `tokens.json`; config mutations go through `with_locked_config` instead, which
+/// holds the lock across the read too so the whole cycle is serialized, not just the final write.
+pub(crate) fn atomic_write(path: &Path, data: &[u8]) -> Result<(), std::io::Error> {
+    let parent = path.parent().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no parent directory")
+    })?;
+    std::fs::create_dir_all(parent)?;
 
-    if path.exists() {
-        let _ = std::fs::remove_file(path);
+    let _lock = lock_dir(parent)?;
+    atomic_write_unlocked(path, data)
+}
+
+#[cfg(unix)]
+fn fsync_dir(dir: &Path) {
+    if let Ok(dir_file) = std::fs::File::open(dir) {
+        let _ = dir_file.sync_all();
     }
+}
 
-    std::fs::rename(&tmp_path, path)?;
+#[cfg(not(unix))]
+fn fsync_dir(_dir: &Path) {}
+
+fn read_config(path: &Path) -> Result<Option<AppConfig>, ConfigError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let data = std::fs::read(path)?;
+    let cfg: AppConfig = serde_json::from_slice(&data)?;
+    Ok(Some(cfg))
+}
+
+fn write_config_locked(path: &Path, cfg: &AppConfig) -> Result<(), ConfigError> {
+    let json = serde_json::to_string_pretty(cfg).map(|s| format!("{s}\n"))?;
+    atomic_write_unlocked(path, json.as_bytes())?;
     Ok(())
 }
 
+/// Locks `config.json`'s directory, hands `mutate` the current config (`None` if it doesn't exist
+/// yet), and persists whatever it returns before releasing the lock — so a command's whole
+/// read-modify-write cycle is serialized against every other command racing on `config.json`, not
+/// just the final write. `mutate` returns `None` in place of the new config to skip the write
+/// entirely, e.g. when there's nothing to update yet.
+fn with_locked_config<T>(
+    mutate: impl FnOnce(Option<AppConfig>) -> Result<(Option<AppConfig>, T), ConfigError>,
+) -> Result<T, ConfigError> {
+    let path = config_file_path()?;
+    let parent = path.parent().expect("config path always has a parent");
+    std::fs::create_dir_all(parent)?;
+
+    let _lock = lock_dir(parent)?;
+
+    let cfg = read_config(&path)?;
+    let (new_cfg, result) = mutate(cfg)?;
+    if let Some(new_cfg) = new_cfg {
+        write_config_locked(&path, &new_cfg)?;
+    }
+    Ok(result)
+}
+
 #[tauri::command]
 pub fn get_config_path() -> Result<String, String> {
     config_file_path()
@@ -80,31 +239,298 @@ pub fn get_config_path() -> Result<String, String> {
         .map_err(|e| e.to_string())
 }
 
+pub(crate) fn load() -> Result<Option<AppConfig>, ConfigError> {
+    read_config(&config_file_path()?)
+}
+
 #[tauri::command]
 pub fn load_config() -> Result<Option<AppConfig>, String> {
-    let path = config_file_path().map_err(|e| e.to_string())?;
-    if !path.exists() {
-        return Ok(None);
-    }
-
-    let data = std::fs::read(path).map_err(|e| e.to_string())?;
-    let cfg: AppConfig = serde_json::from_slice(&data).map_err(|e| e.to_string())?;
-    Ok(Some(cfg))
+    load().map_err(|e| e.to_string())
 }
 
+/// Saves `kiro_server_url` as the base URL of the active profile, creating a `"default"` profile
+/// if none exists yet. Kept for simple single-backend setups; use `add_profile` /
+/// `set_active_profile` to manage more than one.
 #[tauri::command]
 pub fn save_config(kiro_server_url: String) -> Result<String, String> {
     let normalized = normalize_base_url(&kiro_server_url).map_err(|e| e.to_string())?;
-    let cfg = AppConfig {
-        kiro_server_url: normalized.clone(),
+
+    with_locked_config(|cfg| {
+        let mut cfg = cfg.unwrap_or(AppConfig {
+            profiles: Vec::new(),
+            active_profile: DEFAULT_PROFILE_NAME.to_string(),
+            tls: None,
+        });
+
+        match cfg.active_profile_mut() {
+            Some(profile) => profile.base_url = normalized.clone(),
+            None => {
+                cfg.active_profile = DEFAULT_PROFILE_NAME.to_string();
+                cfg.profiles.push(ServerProfile {
+                    name: DEFAULT_PROFILE_NAME.to_string(),
+                    base_url: normalized.clone(),
+                    token: None,
+                    headers: None,
+                });
+            }
+        }
+
+        Ok((Some(cfg), ()))
+    })
+    .map_err(|e| e.to_string())?;
+
+    Ok(normalized)
+}
+
+/// Returns the currently active profile, if `config.json` exists and names one.
+pub(crate) fn active_profile() -> Result<Option<ServerProfile>, ConfigError> {
+    let path = config_file_path()?;
+    let cfg = match read_config(&path)? {
+        Some(cfg) => cfg,
+        None => return Ok(None),
     };
+    Ok(cfg
+        .profiles
+        .into_iter()
+        .find(|p| p.name == cfg.active_profile))
+}
+
+/// Persists `token` on the profile whose `base_url` matches `server_url` (the server a login was
+/// started against), falling back to the active profile if none matches. A no-op if there are no
+/// profiles to attach the token to, e.g. a login started before `save_config`/`add_profile` ran.
+pub(crate) fn store_profile_token(server_url: &str, token: &str) -> Result<(), ConfigError> {
+    with_locked_config(|cfg| {
+        let Some(mut cfg) = cfg else {
+            return Ok((None, ()));
+        };
 
-    let json = serde_json::to_string_pretty(&cfg)
-        .map(|s| format!("{s}\n"))
-        .map_err(|e| e.to_string())?;
+        let target_name = cfg
+            .profiles
+            .iter()
+            .find(|p| p.base_url == server_url)
+            .or_else(|| cfg.profiles.iter().find(|p| p.name == cfg.active_profile))
+            .map(|p| p.name.clone());
+
+        let Some(target_name) = target_name else {
+            return Ok((None, ()));
+        };
+
+        if let Some(profile) = cfg.profiles.iter_mut().find(|p| p.name == target_name) {
+            profile.token = Some(token.to_string());
+        }
 
+        Ok((Some(cfg), ()))
+    })
+}
+
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<ServerProfile>, String> {
     let path = config_file_path().map_err(|e| e.to_string())?;
-    atomic_write(&path, json.as_bytes()).map_err(|e| e.to_string())?;
-    Ok(normalized)
+    Ok(read_config(&path)
+        .map_err(|e| e.to_string())?
+        .map(|cfg| cfg.profiles)
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub fn add_profile(name: String, base_url: String) -> Result<ServerProfile, String> {
+    let normalized = normalize_base_url(&base_url).map_err(|e| e.to_string())?;
+
+    with_locked_config(|cfg| {
+        let mut cfg = cfg.unwrap_or(AppConfig {
+            profiles: Vec::new(),
+            active_profile: name.clone(),
+            tls: None,
+        });
+
+        if cfg.profiles.iter().any(|p| p.name == name) {
+            return Err(ConfigError::DuplicateProfile(name.clone()));
+        }
+
+        let profile = ServerProfile {
+            name: name.clone(),
+            base_url: normalized.clone(),
+            token: None,
+            headers: None,
+        };
+        cfg.profiles.push(profile.clone());
+        if cfg.profiles.len() == 1 {
+            cfg.active_profile = name.clone();
+        }
+
+        Ok((Some(cfg), profile))
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// If `removed_name` was the active profile, falls back to the first remaining profile (or no
+/// profile at all, if none are left).
+fn reassign_active_after_removal(cfg: &mut AppConfig, removed_name: &str) {
+    if cfg.active_profile == removed_name {
+        cfg.active_profile = cfg
+            .profiles
+            .first()
+            .map(|p| p.name.clone())
+            .unwrap_or_default();
+    }
 }
 
+#[tauri::command]
+pub fn remove_profile(name: String) -> Result<(), String> {
+    with_locked_config(|cfg| {
+        let mut cfg = cfg.ok_or_else(|| ConfigError::UnknownProfile(name.clone()))?;
+
+        let before = cfg.profiles.len();
+        cfg.profiles.retain(|p| p.name != name);
+        if cfg.profiles.len() == before {
+            return Err(ConfigError::UnknownProfile(name.clone()));
+        }
+
+        reassign_active_after_removal(&mut cfg, &name);
+
+        Ok((Some(cfg), ()))
+    })
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_active_profile(name: String) -> Result<(), String> {
+    with_locked_config(|cfg| {
+        let mut cfg = cfg.ok_or_else(|| ConfigError::UnknownProfile(name.clone()))?;
+
+        if !cfg.profiles.iter().any(|p| p.name == name) {
+            return Err(ConfigError::UnknownProfile(name.clone()));
+        }
+
+        cfg.active_profile = name.clone();
+        Ok((Some(cfg), ()))
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Sets the extra CA bundle and/or client certificate used for every request to a Kiro server.
+/// Pass `None` for a field to clear it.
+#[tauri::command]
+pub fn set_tls_config(
+    extra_ca_bundle: Option<String>,
+    client_cert: Option<String>,
+    client_key: Option<String>,
+) -> Result<(), String> {
+    with_locked_config(|cfg| {
+        let mut cfg = cfg.unwrap_or(AppConfig {
+            profiles: Vec::new(),
+            active_profile: DEFAULT_PROFILE_NAME.to_string(),
+            tls: None,
+        });
+
+        cfg.tls = Some(TlsConfig {
+            extra_ca_bundle,
+            client_cert,
+            client_key,
+        });
+
+        Ok((Some(cfg), ()))
+    })
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_single_url_config_migrates_to_a_default_profile() {
+        let cfg: AppConfig =
+            serde_json::from_str(r#"{"kiro_server_url":"https://kiro.example.com"}"#).unwrap();
+
+        assert_eq!(cfg.active_profile, DEFAULT_PROFILE_NAME);
+        assert_eq!(cfg.profiles.len(), 1);
+        assert_eq!(cfg.profiles[0].name, DEFAULT_PROFILE_NAME);
+        assert_eq!(cfg.profiles[0].base_url, "https://kiro.example.com");
+        assert!(cfg.profiles[0].token.is_none());
+    }
+
+    #[test]
+    fn current_shape_config_loads_without_migration() {
+        let cfg: AppConfig = serde_json::from_str(
+            r#"{"profiles":[{"name":"staging","base_url":"https://staging.example.com"}],"active_profile":"staging"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(cfg.active_profile, "staging");
+        assert_eq!(cfg.profiles.len(), 1);
+        assert_eq!(cfg.profiles[0].name, "staging");
+    }
+
+    fn profile(name: &str) -> ServerProfile {
+        ServerProfile {
+            name: name.to_string(),
+            base_url: format!("https://{name}.example.com"),
+            token: None,
+            headers: None,
+        }
+    }
+
+    #[test]
+    fn removing_the_active_profile_falls_back_to_the_first_remaining() {
+        let mut cfg = AppConfig {
+            profiles: vec![profile("a"), profile("b")],
+            active_profile: "a".to_string(),
+            tls: None,
+        };
+        cfg.profiles.retain(|p| p.name != "a");
+
+        reassign_active_after_removal(&mut cfg, "a");
+
+        assert_eq!(cfg.active_profile, "b");
+    }
+
+    #[test]
+    fn removing_an_inactive_profile_leaves_active_profile_untouched() {
+        let mut cfg = AppConfig {
+            profiles: vec![profile("a"), profile("b")],
+            active_profile: "a".to_string(),
+            tls: None,
+        };
+        cfg.profiles.retain(|p| p.name != "b");
+
+        reassign_active_after_removal(&mut cfg, "b");
+
+        assert_eq!(cfg.active_profile, "a");
+    }
+
+    #[test]
+    fn removing_the_last_profile_clears_active_profile() {
+        let mut cfg = AppConfig {
+            profiles: vec![profile("a")],
+            active_profile: "a".to_string(),
+            tls: None,
+        };
+        cfg.profiles.retain(|p| p.name != "a");
+
+        reassign_active_after_removal(&mut cfg, "a");
+
+        assert_eq!(cfg.active_profile, "");
+    }
+
+    #[test]
+    fn atomic_write_round_trips_and_leaves_no_temp_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "antihook-atomic-write-test-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("config.json");
+
+        atomic_write(&path, b"first").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"first");
+
+        // A second write over the same file should still succeed and fully replace the contents,
+        // proving the rename-over-existing-file path works without the old remove_file step.
+        atomic_write(&path, b"second").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert!(!path.with_extension("json.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,30 +1,107 @@
 use serde::Serialize;
+use std::collections::HashMap;
 use std::time::Instant;
 
-use crate::config::normalize_base_url;
+use crate::config::{active_profile, load as load_config, normalize_base_url};
+use crate::tls::HttpClientCache;
 
-#[derive(Debug, Serialize)]
+/// Classifies why a health probe did or didn't succeed, so the UI can tell "server up but I'm
+/// not logged in" apart from "wrong path" or "server down".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    AuthRequired,
+    NotFound,
+    ServerError,
+    NetworkError,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct HealthCheckResult {
     pub request_url: String,
     pub ok: bool,
+    pub status: HealthStatus,
     pub status_code: Option<u16>,
     pub elapsed_ms: u128,
     pub payload: Option<serde_json::Value>,
     pub error: Option<String>,
 }
 
+fn classify(status_code: u16, ok: bool) -> (HealthStatus, Option<String>) {
+    if ok {
+        return (HealthStatus::Ok, None);
+    }
+
+    match status_code {
+        401 | 403 => (
+            HealthStatus::AuthRequired,
+            Some(format!("{status_code}: authentication required")),
+        ),
+        404 => (HealthStatus::NotFound, Some("endpoint not found".into())),
+        _ => (
+            HealthStatus::ServerError,
+            Some(format!("server responded with {status_code}")),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_success_status_is_ok_with_no_error() {
+        assert_eq!(classify(200, true), (HealthStatus::Ok, None));
+    }
+
+    #[test]
+    fn classify_401_and_403_are_auth_required() {
+        for status_code in [401, 403] {
+            let (status, error) = classify(status_code, false);
+            assert_eq!(status, HealthStatus::AuthRequired);
+            assert!(error.is_some());
+        }
+    }
+
+    #[test]
+    fn classify_404_is_not_found() {
+        let (status, error) = classify(404, false);
+        assert_eq!(status, HealthStatus::NotFound);
+        assert!(error.is_some());
+    }
+
+    #[test]
+    fn classify_other_failure_is_server_error() {
+        let (status, error) = classify(500, false);
+        assert_eq!(status, HealthStatus::ServerError);
+        assert!(error.is_some());
+    }
+}
+
 async fn fetch_health(
     client: &reqwest::Client,
     request_url: String,
+    token: Option<&str>,
+    headers: &HashMap<String, String>,
 ) -> HealthCheckResult {
     let start = Instant::now();
 
-    let resp = match client.get(&request_url).send().await {
+    let mut req = client.get(&request_url);
+    if let Some(token) = token {
+        req = req.bearer_auth(token);
+    }
+    for (name, value) in headers {
+        req = req.header(name, value);
+    }
+
+    let resp = match req.send().await {
         Ok(r) => r,
         Err(e) => {
             return HealthCheckResult {
                 request_url,
                 ok: false,
+                status: HealthStatus::NetworkError,
                 status_code: None,
                 elapsed_ms: start.elapsed().as_millis(),
                 payload: None,
@@ -33,32 +110,59 @@ async fn fetch_health(
         }
     };
 
-    let status_code = Some(resp.status().as_u16());
+    let status_code = resp.status().as_u16();
     let ok = resp.status().is_success();
     let text = resp.text().await.unwrap_or_default();
     let payload = serde_json::from_str::<serde_json::Value>(&text).ok();
+    let (status, error) = classify(status_code, ok);
 
     HealthCheckResult {
         request_url,
         ok,
-        status_code,
+        status,
+        status_code: Some(status_code),
         elapsed_ms: start.elapsed().as_millis(),
         payload,
-        error: None,
+        error,
     }
 }
 
 /// 检测 `GET /api/health`，并在需要时自动兼容 AntiHub Web 的 `/backend/*` 代理：
 /// - `{base}/api/health`
 /// - `{base}/backend/api/health`
+///
+/// `token` and `headers` are attached to every candidate request; when either is `None`, the
+/// active profile's stored token and/or header map (if any) is used instead. The underlying
+/// `reqwest::Client` is reused across calls via `client_cache` rather than rebuilt per check.
 #[tauri::command]
-pub async fn check_health(base_url: String) -> Result<HealthCheckResult, String> {
+pub async fn check_health(
+    base_url: String,
+    token: Option<String>,
+    headers: Option<HashMap<String, String>>,
+    client_cache: tauri::State<'_, HttpClientCache>,
+) -> Result<HealthCheckResult, String> {
     let base = normalize_base_url(&base_url).map_err(|e| e.to_string())?;
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(8))
-        .build()
-        .map_err(|e| e.to_string())?;
+    let profile = active_profile().map_err(|e| e.to_string())?;
+    let token = token.or_else(|| profile.as_ref().and_then(|p| p.token.clone()));
+    let headers =
+        headers.unwrap_or_else(|| profile.and_then(|p| p.headers).unwrap_or_default());
+
+    let tls_cfg = load_config().map_err(|e| e.to_string())?.and_then(|c| c.tls);
+    let client = match client_cache.get_or_build(tls_cfg.as_ref(), std::time::Duration::from_secs(8)) {
+        Ok(client) => client,
+        Err(e) => {
+            return Ok(HealthCheckResult {
+                request_url: format!("{base}/api/health"),
+                ok: false,
+                status: HealthStatus::NetworkError,
+                status_code: None,
+                elapsed_ms: 0,
+                payload: None,
+                error: Some(format!("tls setup failed: {e}")),
+            });
+        }
+    };
 
     let candidates = [
         format!("{base}/api/health"),
@@ -69,9 +173,9 @@ pub async fn check_health(base_url: String) -> Result<HealthCheckResult, String>
     let mut last: Option<HealthCheckResult> = None;
 
     for (idx, url) in candidates.iter().cloned().enumerate() {
-        let result = fetch_health(&client, url).await;
+        let result = fetch_health(&client, url, token.as_deref(), &headers).await;
         let should_try_next = idx + 1 < candidate_count
-            && (result.status_code == Some(404) || result.status_code.is_none());
+            && matches!(result.status, HealthStatus::NotFound | HealthStatus::NetworkError);
 
         if result.ok {
             return Ok(result);
@@ -87,6 +191,7 @@ pub async fn check_health(base_url: String) -> Result<HealthCheckResult, String>
     Ok(last.unwrap_or(HealthCheckResult {
         request_url: format!("{base}/api/health"),
         ok: false,
+        status: HealthStatus::NetworkError,
         status_code: None,
         elapsed_ms: 0,
         payload: None,
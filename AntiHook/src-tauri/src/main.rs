@@ -1,15 +1,31 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod auth;
 mod config;
 mod health;
+mod monitor;
+mod tls;
 
 fn main() {
     tauri::Builder::default()
+        .manage(auth::AuthManager::default())
+        .manage(monitor::MonitorManager::default())
+        .manage(tls::HttpClientCache::default())
         .invoke_handler(tauri::generate_handler![
             config::get_config_path,
             config::load_config,
             config::save_config,
-            health::check_health
+            config::list_profiles,
+            config::add_profile,
+            config::remove_profile,
+            config::set_active_profile,
+            config::set_tls_config,
+            health::check_health,
+            auth::begin_login,
+            auth::complete_login,
+            monitor::start_monitor,
+            monitor::stop_monitor,
+            monitor::get_health_history
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
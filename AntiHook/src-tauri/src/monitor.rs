@@ -0,0 +1,172 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rand::Rng;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::config;
+use crate::health::{check_health, HealthCheckResult, HealthStatus};
+use crate::tls::HttpClientCache;
+
+const HISTORY_CAPACITY: usize = 100;
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_CAP: Duration = Duration::from_secs(60);
+const BACKOFF_JITTER_MS: u64 = 250;
+const HEALTH_EVENT: &str = "health://update";
+
+/// Holds the background polling task (if running) and a bounded history of recent results for
+/// the history/sparkline view.
+#[derive(Default)]
+pub struct MonitorManager {
+    task: Mutex<Option<JoinHandle<()>>>,
+    history: Arc<Mutex<VecDeque<HealthCheckResult>>>,
+}
+
+async fn push_history(history: &Mutex<VecDeque<HealthCheckResult>>, result: HealthCheckResult) {
+    let mut buf = history.lock().await;
+    if buf.len() == HISTORY_CAPACITY {
+        buf.pop_front();
+    }
+    buf.push_back(result);
+}
+
+async fn sleep_with_jitter(backoff: Duration) {
+    let jitter_ms = rand::thread_rng().gen_range(0..BACKOFF_JITTER_MS);
+    tokio::time::sleep(backoff + Duration::from_millis(jitter_ms)).await;
+}
+
+/// Re-reads the active profile on every iteration (rather than snapshotting it once when the
+/// monitor starts) so a token refreshed mid-poll — e.g. via `complete_login` while the monitor is
+/// already running — takes effect on the very next check instead of requiring a restart.
+async fn monitor_loop(
+    app: AppHandle,
+    interval: Duration,
+    history: Arc<Mutex<VecDeque<HealthCheckResult>>>,
+) {
+    let mut backoff = BACKOFF_BASE;
+    loop {
+        let profile = config::active_profile().ok().flatten();
+        let client_cache = app.state::<HttpClientCache>();
+
+        let result = match &profile {
+            Some(profile) => match check_health(
+                profile.base_url.clone(),
+                profile.token.clone(),
+                profile.headers.clone(),
+                client_cache,
+            )
+            .await
+            {
+                Ok(result) => result,
+                Err(err) => HealthCheckResult {
+                    request_url: profile.base_url.clone(),
+                    ok: false,
+                    status: HealthStatus::NetworkError,
+                    status_code: None,
+                    elapsed_ms: 0,
+                    payload: None,
+                    error: Some(err),
+                },
+            },
+            None => HealthCheckResult {
+                request_url: String::new(),
+                ok: false,
+                status: HealthStatus::NetworkError,
+                status_code: None,
+                elapsed_ms: 0,
+                payload: None,
+                error: Some("no active server profile configured".to_string()),
+            },
+        };
+
+        let ok = result.ok;
+        push_history(&history, result.clone()).await;
+        let _ = app.emit_all(HEALTH_EVENT, &result);
+
+        if ok {
+            backoff = BACKOFF_BASE;
+            tokio::time::sleep(interval).await;
+        } else {
+            sleep_with_jitter(backoff).await;
+            backoff = (backoff * 2).min(BACKOFF_CAP);
+        }
+    }
+}
+
+/// Starts polling the active profile's health endpoints every `interval_secs`, emitting a
+/// `health://update` event after each attempt. Failing checks back off exponentially (capped,
+/// with jitter) instead of retrying at a fixed rate; a success resets the backoff.
+#[tauri::command]
+pub async fn start_monitor(
+    app: AppHandle,
+    interval_secs: u64,
+    monitor: tauri::State<'_, MonitorManager>,
+) -> Result<(), String> {
+    config::active_profile()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "no active server profile configured".to_string())?;
+
+    let mut task_guard = monitor.task.lock().await;
+    if task_guard.is_some() {
+        return Err("health monitor is already running".into());
+    }
+
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let history = monitor.history.clone();
+    *task_guard = Some(tokio::spawn(monitor_loop(app, interval, history)));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_monitor(monitor: tauri::State<'_, MonitorManager>) -> Result<(), String> {
+    if let Some(handle) = monitor.task.lock().await.take() {
+        handle.abort();
+    }
+    Ok(())
+}
+
+/// Returns the bounded history of recent results, oldest first, for history/sparkline display.
+#[tauri::command]
+pub async fn get_health_history(
+    monitor: tauri::State<'_, MonitorManager>,
+) -> Result<Vec<HealthCheckResult>, String> {
+    Ok(monitor.history.lock().await.iter().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_result(request_url: &str) -> HealthCheckResult {
+        HealthCheckResult {
+            request_url: request_url.to_string(),
+            ok: true,
+            status: HealthStatus::Ok,
+            status_code: Some(200),
+            elapsed_ms: 0,
+            payload: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn push_history_evicts_the_oldest_entry_once_at_capacity() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let history: Mutex<VecDeque<HealthCheckResult>> = Mutex::new(VecDeque::new());
+            for i in 0..HISTORY_CAPACITY + 1 {
+                push_history(&history, sample_result(&i.to_string())).await;
+            }
+
+            let buf = history.lock().await;
+            assert_eq!(buf.len(), HISTORY_CAPACITY);
+            assert_eq!(buf.front().unwrap().request_url, "1");
+            assert_eq!(buf.back().unwrap().request_url, HISTORY_CAPACITY.to_string());
+        });
+    }
+}
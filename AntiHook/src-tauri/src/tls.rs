@@ -0,0 +1,123 @@
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Extra trust material layered on top of the OS trust store when talking to a Kiro server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub extra_ca_bundle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_cert: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_key: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("io error reading TLS material: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to load native root certificates: {0}")]
+    NativeRoots(String),
+
+    #[error("invalid CA bundle: {0}")]
+    InvalidCaBundle(String),
+
+    #[error("invalid client certificate/key: {0}")]
+    InvalidIdentity(String),
+
+    #[error("http client error: {0}")]
+    Client(#[from] reqwest::Error),
+}
+
+/// Builds a `reqwest::Client` that trusts the OS root store (loaded via `rustls-native-certs`)
+/// plus, when configured, an extra CA bundle and a client identity for mutual TLS.
+pub fn build_http_client(
+    tls: Option<&TlsConfig>,
+    timeout: Duration,
+) -> Result<reqwest::Client, TlsError> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(timeout)
+        .tls_built_in_root_certs(false);
+
+    for native_cert in
+        rustls_native_certs::load_native_certs().map_err(|e| TlsError::NativeRoots(e.to_string()))?
+    {
+        if let Ok(cert) = reqwest::Certificate::from_der(&native_cert.0) {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    if let Some(tls) = tls {
+        if let Some(path) = &tls.extra_ca_bundle {
+            let pem = fs::read(path)?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| TlsError::InvalidCaBundle(e.to_string()))?;
+            builder = builder.add_root_certificate(cert);
+        }
+
+        if let (Some(cert_path), Some(key_path)) = (&tls.client_cert, &tls.client_key) {
+            let mut pem = fs::read(cert_path)?;
+            pem.extend(fs::read(key_path)?);
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| TlsError::InvalidIdentity(e.to_string()))?;
+            builder = builder.identity(identity);
+        }
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Caches the last `reqwest::Client` built by `build_http_client`, keyed on the `TlsConfig`/
+/// timeout it was built for. `reqwest::Client` holds its own connection pool, so reusing one
+/// across health checks avoids re-parsing the OS trust store and re-handshaking on every poll;
+/// it's only rebuilt when the TLS config or timeout actually changes.
+#[derive(Default)]
+pub struct HttpClientCache {
+    cached: Mutex<Option<(Option<TlsConfig>, Duration, reqwest::Client)>>,
+}
+
+impl HttpClientCache {
+    pub fn get_or_build(
+        &self,
+        tls: Option<&TlsConfig>,
+        timeout: Duration,
+    ) -> Result<reqwest::Client, TlsError> {
+        let mut cached = self.cached.lock().unwrap();
+        if let Some((cached_tls, cached_timeout, client)) = cached.as_ref() {
+            if cached_tls.as_ref() == tls && *cached_timeout == timeout {
+                return Ok(client.clone());
+            }
+        }
+
+        let client = build_http_client(tls, timeout)?;
+        *cached = Some((tls.cloned(), timeout, client.clone()));
+        Ok(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_http_client_succeeds_with_no_tls_config() {
+        assert!(build_http_client(None, Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn build_http_client_fails_on_a_missing_ca_bundle() {
+        let tls = TlsConfig {
+            extra_ca_bundle: Some("/nonexistent/antihook-test-ca.pem".to_string()),
+            client_cert: None,
+            client_key: None,
+        };
+
+        let err = build_http_client(Some(&tls), Duration::from_secs(5)).unwrap_err();
+        assert!(matches!(err, TlsError::Io(_)));
+    }
+}